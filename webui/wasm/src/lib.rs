@@ -1,12 +1,41 @@
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use js_sys::Array;
 
 extern crate console_error_panic_hook;
 use std::panic;
 
+// Rubber-band zoom state.  ZOOM holds the currently selected relative (x_min, x_max, y_min, y_max)
+// window, each in [0, 1], which draw_bar_chart narrows its rendering down to.  PLOT_RECT records
+// the pixel bounds of the plot area (left, right, top, bottom) from the most recent draw_bar_chart
+// call, so a drag selection in pixel space can be converted back into the relative window.
+thread_local! {
+    static ZOOM: RefCell<(f64, f64, f64, f64)> = RefCell::new((0.0, 1.0, 0.0, 1.0));
+    static PLOT_RECT: RefCell<(f64, f64, f64, f64)> = RefCell::new((0.0, 0.0, 0.0, 0.0));
+    // BAR_HITS holds each bar's hit-rectangle (category name, count, x-range, top-y) from the most
+    // recent draw_bar_chart call, so the hover handler can find which bar the pointer is over
+    // without recomputing the whole layout.  BASE_IMAGE caches the rendered chart, so the hover
+    // handler can restore it before drawing a crosshair/tooltip overlay on top.
+    static BAR_HITS: RefCell<Vec<BarHit>> = RefCell::new(vec![]);
+    static BASE_IMAGE: RefCell<Option<web_sys::ImageData>> = RefCell::new(None);
+    // HOVER_HANDLER_REGISTERED tracks whether register_hover_handler has already attached its
+    // mousemove listener, so calling it again (e.g. after a redraw) doesn't stack up listeners.
+    static HOVER_HANDLER_REGISTERED: RefCell<bool> = RefCell::new(false);
+}
+
+// BarHit is the hit-rectangle recorded for one drawn bar, used by the hover crosshair/tooltip.
+struct BarHit {
+    name: String,
+    count: u32,
+    left: f64,
+    right: f64,
+    top: f64,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Record {
     Name: String,
@@ -50,6 +79,58 @@ enum OrderDirection {
     OrderAscending = 1,
 }
 
+// BarMode selects how a category's series are combined into a bar: Stacked accumulates each
+// series as a segment of one bar, Grouped draws each series as its own bar side by side.  Mirrors
+// the histogram/series support in plotters.
+#[derive(Clone, Copy, PartialEq)]
+enum BarMode {
+    Stacked = 0,
+    Grouped = 1,
+}
+
+// DEFAULT_SERIES is the synthetic series name used when the caller doesn't supply a series_col,
+// so a plain single-count-per-category chart is just the degenerate one-series case of the
+// grouped/stacked machinery.
+const DEFAULT_SERIES: &str = "Total";
+
+// CategoryBars is one category's row of data for the bar chart: its per-series values and total.
+struct CategoryBars {
+    name: String,
+    series_values: Vec<(String, u32)>,
+    total: u32,
+}
+
+// LegendPosition mirrors the positioned-legend concept (TOP/BOTTOM/LEFT/RIGHT) used by Cairo-based charting libraries.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+enum LegendPosition {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+// Legend configures an optional key, mapping colours back to category names (or to series names,
+// for a chart with a series column).
+#[derive(Serialize, Deserialize)]
+pub struct Legend {
+    position: LegendPosition,
+    border: bool,
+    background: Option<String>,
+}
+
+// BarChartConfig bundles draw_bar_chart's data-shape and ordering knobs into a single JsValue
+// argument, the same way Legend does for the legend settings.  Callers build this as a plain JS
+// object: { order_by, order_direction, cat_col, val_col, series_col, bar_mode }.
+#[derive(Serialize, Deserialize)]
+pub struct BarChartConfig {
+    order_by: u32,
+    order_direction: u32,
+    cat_col: u32,
+    val_col: u32,
+    series_col: i32,
+    bar_mode: u32,
+}
+
 const GOLDEN_RATIO_CONJUGATE: f64 = 0.6180;
 const DEBUG: bool = false;
 
@@ -64,23 +145,18 @@ fn document() -> web_sys::Document {
         .expect("should have a document on window")
 }
 
-// draw_bar_chart draws a simple bar chart, with a colour palette generated from the provided seed value
-#[wasm_bindgen]
-pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_direction: u32) {
-    // Show better panic messages on the javascript console.  Useful for development
-    panic::set_hook(Box::new(console_error_panic_hook::hook));
-
-    // * Import the data from the web page *
-    let data: DbData = js_data.into_serde().unwrap();
-    let rows = data.Records;
-
-    // Count the number of items for each category
+// build_item_counts aggregates the rows of a query result into a per-category count, along with
+// the highest count seen.  Used by the pie chart renderer; the bar chart aggregates per series
+// with build_series_counts instead.
+fn build_item_counts(rows: &[Vec<Record>], cat_col: usize, val_col: usize) -> (HashMap<&String, u32>, u32) {
     let mut highest_val = 0;
     let mut item_counts: HashMap<&String, u32> = HashMap::new();
-    for row in &rows {
-        let cat_name = &row[10].Value;
-        let item_count = &row[12].Value;
-        let item_count: u32 = item_count.parse().unwrap();
+    for row in rows {
+        let cat_name = &row[cat_col].Value;
+        let item_count: u32 = match row[val_col].Value.parse() {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
         if item_counts.contains_key(&cat_name) {
             let c = item_counts[cat_name];
             item_counts.insert(cat_name, c + item_count);
@@ -111,31 +187,54 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
         web_sys::console::log_2(&"Highest count: ".into(), &highest_val.into());
     }
 
-    // * Sort the category data, so the draw order of bars doesn't change when the browser window is resized *
+    (item_counts, highest_val)
+}
 
-    let mut draw_order: Vec<DrawObject> = vec![];
-    for (label, num) in &item_counts {
-        draw_order.push(DrawObject::new(label.to_string(), num.clone()));
-    }
+// build_series_counts aggregates the rows of a query result into a per-category, per-series count,
+// for the stacked/grouped bar chart modes.  series_order lists the series in first-seen order, so
+// every category subdivides its bar the same way.
+fn build_series_counts(
+    rows: &[Vec<Record>],
+    cat_col: usize,
+    val_col: usize,
+    series_col: Option<usize>,
+) -> (HashMap<String, HashMap<String, u32>>, Vec<String>) {
+    let mut counts: HashMap<String, HashMap<String, u32>> = HashMap::new();
+    let mut series_order: Vec<String> = vec![];
+    for row in rows {
+        let cat_name = row[cat_col].Value.clone();
+        let item_count: u32 = match row[val_col].Value.parse() {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let series_name = match series_col {
+            Some(col) => row[col].Value.clone(),
+            None => DEFAULT_SERIES.to_string(),
+        };
+        if !series_order.contains(&series_name) {
+            series_order.push(series_name.clone());
+        }
 
-    // Sort by the users chosen sort order
-    if order_by == OrderBy::CategoryName as u32 {
-        // Sort by category name
-        draw_order.sort_by(|a, b| b.name.cmp(&a.name));
-    } else {
-        // Sort by item total
-        draw_order.sort_by(|a, b| b.num.cmp(&a.num));
+        let series_counts = counts.entry(cat_name).or_insert_with(HashMap::new);
+        *series_counts.entry(series_name).or_insert(0) += item_count;
     }
 
-    // Reverse the sort order if desired
-    if order_direction == OrderDirection::OrderDescending as u32 {
-        draw_order.reverse();
-    }
+    (counts, series_order)
+}
 
-    // * Canvas setup *
+// trim_title strips the common sqlite file extensions off a database title, so the chart heading
+// reads as a plain database name rather than a filename.
+fn trim_title(title: &str) -> &str {
+    let title = title.trim_end_matches(".sqlite");
+    let title = title.trim_end_matches(".sqlite3");
+    title.trim_end_matches(".db")
+}
 
+// setup_canvas fetches the named canvas element and its 2D drawing context, resizing the canvas
+// to match the current browser window if it doesn't already.
+fn setup_canvas(element_id: &str) -> (web_sys::HtmlCanvasElement, web_sys::CanvasRenderingContext2d, f64, f64) {
     let canvas: web_sys::HtmlCanvasElement = document()
-        .get_element_by_id("barchart")
+        .get_element_by_id(element_id)
         .unwrap()
         .dyn_into::<web_sys::HtmlCanvasElement>()
         .unwrap();
@@ -168,13 +267,325 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
         .dyn_into::<web_sys::CanvasRenderingContext2d>()
         .unwrap();
 
+    (canvas, ctx, canvas_width, canvas_height)
+}
+
+// draw_legend renders a key mapping each entry's swatch colour back to its name (a category, or a
+// series for a chart with a series column), inside the given rectangle.  One row is drawn per entry, stacked top to bottom.
+fn draw_legend(
+    ctx: &web_sys::CanvasRenderingContext2d,
+    legend: &Legend,
+    rect: (f64, f64, f64, f64),
+    entries: &[(String, String)],
+    font_height: f64,
+) {
+    let (left, top, right, bottom) = rect;
+
+    if let Some(background) = &legend.background {
+        ctx.set_fill_style(&background.into());
+        ctx.fill_rect(left, top, right - left, bottom - top);
+    }
+    if legend.border {
+        ctx.set_line_width(1.0);
+        ctx.set_stroke_style(&"black".into());
+        ctx.stroke_rect(left, top, right - left, bottom - top);
+    }
+
+    let padding = font_height * 0.5;
+    let swatch_size = font_height;
+    let row_height = font_height + padding;
+    let mut row_top = top + padding;
+
+    ctx.set_font(&format!("{}pt serif", font_height));
+    ctx.set_text_align(&"left");
+    for (name, color) in entries {
+        ctx.set_fill_style(&color.into());
+        ctx.fill_rect(left + padding, row_top, swatch_size, swatch_size);
+        ctx.set_stroke_style(&"black".into());
+        ctx.set_line_width(1.0);
+        ctx.stroke_rect(left + padding, row_top, swatch_size, swatch_size);
+
+        ctx.set_fill_style(&"black".into());
+        ctx.fill_text(name, left + padding + swatch_size + padding, row_top + swatch_size);
+
+        row_top += row_height;
+    }
+}
+
+// zoom_to_selection narrows the rubber-band zoom window to the pixel rectangle the user just
+// dragged out, (x0, y0) to (x1, y1), over the plot area most recently drawn by draw_bar_chart.
+// Mirrors the relative-zoom approach used by Cairo-Chart.  The caller should re-invoke
+// draw_bar_chart afterwards to render the zoomed-in view.
+#[wasm_bindgen]
+pub fn zoom_to_selection(x0: f64, y0: f64, x1: f64, y1: f64) {
+    let (plot_left, plot_right, plot_top, plot_bottom) = PLOT_RECT.with(|r| *r.borrow());
+    if plot_right <= plot_left || plot_bottom <= plot_top {
+        return;
+    }
+
+    ZOOM.with(|z| {
+        let (rel_x_min, rel_x_max, rel_y_min, rel_y_max) = *z.borrow();
+        let x_span = rel_x_max - rel_x_min;
+        let y_span = rel_y_max - rel_y_min;
+
+        let new_x0 = rel_x_min + (x0 - plot_left) / (plot_right - plot_left) * x_span;
+        let new_x1 = rel_x_min + (x1 - plot_left) / (plot_right - plot_left) * x_span;
+        // Canvas pixel Y grows downward (plot_top is the current window's highest value, plot_bottom
+        // its lowest), which is the opposite of rel_zoom_y's sense, so measure from plot_bottom
+        // rather than plot_top
+        let new_y0 = rel_y_min + (plot_bottom - y0) / (plot_bottom - plot_top) * y_span;
+        let new_y1 = rel_y_min + (plot_bottom - y1) / (plot_bottom - plot_top) * y_span;
+
+        let clamp = |v: f64| v.max(0.0).min(1.0);
+        *z.borrow_mut() = (
+            clamp(new_x0.min(new_x1)),
+            clamp(new_x0.max(new_x1)),
+            clamp(new_y0.min(new_y1)),
+            clamp(new_y0.max(new_y1)),
+        );
+    });
+}
+
+// reset_zoom restores the full [0, 1] zoom window, so the next draw_bar_chart call renders every
+// bar again.
+#[wasm_bindgen]
+pub fn reset_zoom() {
+    ZOOM.with(|z| *z.borrow_mut() = (0.0, 1.0, 0.0, 1.0));
+}
+
+// register_hover_handler attaches a pointer-move listener to the "barchart" canvas, so moving the
+// cursor over a bar draws a crosshair to the axes and a tooltip with the exact category name and
+// count ("cursor crossings", mirroring the Cairo-Chart interaction).  Call this once after the
+// first draw_bar_chart call.
+#[wasm_bindgen]
+pub fn register_hover_handler() {
+    let already_registered = HOVER_HANDLER_REGISTERED.with(|r| r.replace(true));
+    if already_registered {
+        return;
+    }
+
+    let canvas: web_sys::HtmlCanvasElement = document()
+        .get_element_by_id("barchart")
+        .unwrap()
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .unwrap();
+
+    let closure = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+        on_bar_hover(&event);
+    }) as Box<dyn FnMut(_)>);
+    canvas
+        .add_event_listener_with_callback("mousemove", closure.as_ref().unchecked_ref())
+        .unwrap();
+    closure.forget();
+}
+
+// on_bar_hover restores the cached base chart image (wiping away any crosshair/tooltip drawn for
+// a previous pointer position), then - if the pointer is over a bar - draws a crosshair out to
+// the axes and a tooltip box with that bar's category name and exact count.
+fn on_bar_hover(event: &web_sys::MouseEvent) {
+    let canvas: web_sys::HtmlCanvasElement = document()
+        .get_element_by_id("barchart")
+        .unwrap()
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .unwrap();
+    let ctx = canvas
+        .get_context("2d")
+        .unwrap()
+        .unwrap()
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .unwrap();
+
+    let restored = BASE_IMAGE.with(|b| {
+        b.borrow()
+            .as_ref()
+            .map(|image| ctx.put_image_data(image, 0.0, 0.0))
+    });
+    if restored.is_none() {
+        // Nothing has been drawn yet
+        return;
+    }
+
+    let canvas_rect = canvas.get_bounding_client_rect();
+    let x = event.client_x() as f64 - canvas_rect.left();
+    let y = event.client_y() as f64 - canvas_rect.top();
+
+    let hit = BAR_HITS.with(|h| {
+        h.borrow()
+            .iter()
+            .find(|b| x >= b.left && x < b.right)
+            .map(|b| (b.name.clone(), b.count, b.top))
+    });
+    let (name, count, bar_top) = match hit {
+        Some(h) => h,
+        None => return,
+    };
+
+    let (axis_left, axis_right, axis_top, axis_bottom) = PLOT_RECT.with(|r| *r.borrow());
+
+    // Draw the crosshair: a vertical line at the pointer from the bar's top down to the X axis, and
+    // a horizontal line at the pointer's height across the whole plot area
+    let dash = Array::new();
+    dash.push(&"2".into());
+    dash.push(&"2".into());
+    ctx.save();
+    ctx.set_line_width(1.0);
+    ctx.set_stroke_style(&"rgb(80, 80, 80)".into());
+    ctx.set_line_dash(&dash.into());
+    ctx.begin_path();
+    ctx.move_to(x, bar_top);
+    ctx.line_to(x, axis_bottom);
+    ctx.move_to(axis_left, y);
+    ctx.line_to(axis_right, y);
+    ctx.stroke();
+    ctx.restore();
+
+    // Draw the tooltip box, just above and to the right of the pointer
+    let tooltip_text = format!("{}: {}", name, count);
+    ctx.set_font("12pt serif");
+    let text_width = ctx.measure_text(&tooltip_text).unwrap().width();
+    let padding = 6.0;
+    let box_width = text_width + (padding * 2.0);
+    let box_height = 12.0 + (padding * 2.0);
+    let box_left = (x + 10.0).min(axis_right - box_width);
+    let box_top = (y - box_height - 10.0).max(axis_top);
+
+    ctx.set_fill_style(&"rgb(255, 255, 225)".into());
+    ctx.fill_rect(box_left, box_top, box_width, box_height);
+    ctx.set_stroke_style(&"black".into());
+    ctx.set_line_width(1.0);
+    ctx.stroke_rect(box_left, box_top, box_width, box_height);
+
+    ctx.set_fill_style(&"black".into());
+    ctx.set_text_align(&"left");
+    ctx.fill_text(&tooltip_text, box_left + padding, box_top + padding + 12.0);
+}
+
+// draw_bar_chart draws a bar chart, with a colour palette generated from the provided seed value.
+// js_config carries the data-shape and ordering knobs (see BarChartConfig): cat_col and val_col
+// select which columns of the query result hold the category name and the value to sum, so the
+// chart isn't tied to one fixed query shape; series_col optionally selects a second dimension to
+// break each category down by (pass -1 for a plain single-series chart); when given, bar_mode
+// chooses whether each category's series are drawn Stacked or Grouped.
+#[wasm_bindgen]
+pub fn draw_bar_chart(palette: f64, js_data: &JsValue, js_config: &JsValue, js_legend: &JsValue) {
+    // Show better panic messages on the javascript console.  Useful for development
+    panic::set_hook(Box::new(console_error_panic_hook::hook));
+
+    // * Import the data from the web page *
+    let BarChartConfig { order_by, order_direction, cat_col, val_col, series_col, bar_mode } = js_config.into_serde().unwrap();
+    let legend: Option<Legend> = js_legend.into_serde().unwrap_or(None);
+    let mut data: DbData = js_data.into_serde().unwrap();
+    assert!((cat_col as i32) < data.ColCount, "cat_col is beyond the result set's column count");
+    assert!((val_col as i32) < data.ColCount, "val_col is beyond the result set's column count");
+    assert!(series_col < data.ColCount, "series_col is beyond the result set's column count");
+    let cat_col = cat_col as usize;
+    let val_col = val_col as usize;
+    let series_col = if series_col < 0 { None } else { Some(series_col as usize) };
+    let multi_series = series_col.is_some();
+    let bar_mode = if bar_mode == BarMode::Grouped as u32 { BarMode::Grouped } else { BarMode::Stacked };
+
+    // Auto-fill the axis captions from the column names, if the caller didn't provide any
+    if data.XAxisLabel.is_empty() {
+        data.XAxisLabel = data.ColNames[cat_col].clone();
+    }
+    if data.YAxisLabel.is_empty() {
+        data.YAxisLabel = data.ColNames[val_col].clone();
+    }
+
+    let rows = data.Records;
+
+    // Count the number of items for each category/series combination.  A plain single-series chart
+    // is just the degenerate case where every row falls into the one DEFAULT_SERIES series.
+    let (series_counts, series_order) = build_series_counts(&rows, cat_col, val_col, series_col);
+
+    // Determine the highest value the Y axis needs to fit: the highest per-category total for a
+    // stacked chart, or the highest single series value for a grouped one
+    let highest_val = match bar_mode {
+        BarMode::Stacked => series_counts.values().map(|series| series.values().sum::<u32>()).max().unwrap_or(0),
+        BarMode::Grouped => series_counts.values().flat_map(|series| series.values().copied()).max().unwrap_or(0),
+    };
+
+    // * Sort the category data, so the draw order of bars doesn't change when the browser window is resized *
+
+    let mut draw_order: Vec<CategoryBars> = vec![];
+    for (cat_name, series) in &series_counts {
+        let mut series_values: Vec<(String, u32)> = vec![];
+        let mut total = 0;
+        for series_name in &series_order {
+            let count = *series.get(series_name).unwrap_or(&0);
+            series_values.push((series_name.clone(), count));
+            total += count;
+        }
+        draw_order.push(CategoryBars { name: cat_name.clone(), series_values, total });
+    }
+
+    // Sort by the users chosen sort order
+    if order_by == OrderBy::CategoryName as u32 {
+        // Sort by category name
+        draw_order.sort_by(|a, b| b.name.cmp(&a.name));
+    } else {
+        // Sort by item total
+        draw_order.sort_by(|a, b| b.total.cmp(&a.total));
+    }
+
+    // Reverse the sort order if desired
+    if order_direction == OrderDirection::OrderDescending as u32 {
+        draw_order.reverse();
+    }
+
+    // Capture the full, pre-zoom category order before slicing below, so a category's colour (used
+    // for both bars and the legend) stays stable no matter which zoom window is currently active
+    let full_category_order: Vec<String> = draw_order.iter().map(|bar| bar.name.clone()).collect();
+
+    // Apply the current rubber-band zoom window, if any, keeping only the bars that fall within
+    // the selected relative X range
+    let (rel_zoom_x_min, rel_zoom_x_max, rel_zoom_y_min, rel_zoom_y_max) = ZOOM.with(|z| *z.borrow());
+    let total_bars = draw_order.len();
+    if total_bars > 0 {
+        let mut start_idx = (rel_zoom_x_min * total_bars as f64).floor() as usize;
+        let mut end_idx = (rel_zoom_x_max * total_bars as f64).ceil() as usize;
+        start_idx = start_idx.min(total_bars - 1);
+        end_idx = end_idx.max(start_idx + 1).min(total_bars);
+        draw_order = draw_order.into_iter().skip(start_idx).take(end_idx - start_idx).collect();
+    }
+
+    // * Canvas setup *
+
+    let (_canvas, ctx, canvas_width, canvas_height) = setup_canvas("barchart");
+
     // * Bar graph setup *
 
     // Fixed value pieces
     let border = 2.0;
     let area_border = 2.0;
-    let display_width = canvas_width - border - 1.0;
-    let display_height = canvas_height - border - 1.0;
+
+    // If a legend was requested, reserve space for it by shrinking the area the rest of the
+    // chart is laid out in, on whichever side the legend occupies
+    let legend_thickness = match legend.as_ref().map(|l| l.position) {
+        Some(LegendPosition::Left) | Some(LegendPosition::Right) => (canvas_width - border - 1.0) * 0.18,
+        Some(LegendPosition::Top) | Some(LegendPosition::Bottom) => (canvas_height - border - 1.0) * 0.12,
+        None => 0.0,
+    };
+    let (legend_left_inset, legend_right_inset, legend_top_inset, legend_bottom_inset) = match legend.as_ref().map(|l| l.position) {
+        Some(LegendPosition::Left) => (legend_thickness, 0.0, 0.0, 0.0),
+        Some(LegendPosition::Right) => (0.0, legend_thickness, 0.0, 0.0),
+        Some(LegendPosition::Top) => (0.0, 0.0, legend_thickness, 0.0),
+        Some(LegendPosition::Bottom) => (0.0, 0.0, 0.0, legend_thickness),
+        None => (0.0, 0.0, 0.0, 0.0),
+    };
+
+    let display_width = canvas_width - border - 1.0 - legend_left_inset - legend_right_inset;
+    let display_height = canvas_height - border - 1.0 - legend_top_inset - legend_bottom_inset;
+    let origin_x = border + area_border + legend_left_inset;
+    let origin_y = border + area_border + legend_top_inset;
+
+    // The rectangle the legend itself gets drawn into, carved out of the space just reserved above
+    let legend_rect: Option<(f64, f64, f64, f64)> = legend.as_ref().map(|l| match l.position {
+        LegendPosition::Left => (border + area_border, border + area_border, origin_x, canvas_height - border - 1.0),
+        LegendPosition::Right => (origin_x + display_width, border + area_border, origin_x + display_width + legend_thickness, canvas_height - border - 1.0),
+        LegendPosition::Top => (origin_x, border + area_border, origin_x + display_width, origin_y),
+        LegendPosition::Bottom => (origin_x, origin_y + display_height, origin_x + display_width, origin_y + display_height + legend_thickness),
+    });
 
     // Calculate the area available to each of the graph elements
     let graph_space_width = display_width * 0.9; // Graph area is allowed to use 90% of the canvas width.  The side borders get the remaining 10% (5% each)
@@ -193,23 +604,23 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
     let bottom_space_height = top_space_height;
 
     // Derived co-ordinates
-    let left_space_top = border + area_border + top_space_height;
-    let left_space_left = border + area_border;
+    let left_space_top = origin_y + top_space_height;
+    let left_space_left = origin_x;
     let left_space_bottom = left_space_top + left_space_height;
     let left_space_right = left_space_left + left_space_width;
 
-    let right_space_top = border + area_border + top_space_height;
-    let right_space_left = border + area_border + left_space_width + graph_space_width - (area_border * 3.0);
+    let right_space_top = origin_y + top_space_height;
+    let right_space_left = origin_x + left_space_width + graph_space_width - (area_border * 3.0);
     let right_space_bottom = right_space_top + right_space_height;
     let right_space_right = right_space_left + right_space_width;
 
-    let top_space_top = border + area_border;
-    let top_space_left = border + area_border;
+    let top_space_top = origin_y;
+    let top_space_left = origin_x;
     let top_space_bottom = top_space_top + top_space_height;
     let top_space_right = top_space_left + top_space_width - (area_border * 3.0);
 
-    let bottom_space_top = border + area_border + top_space_height + graph_space_height;
-    let bottom_space_left = border + area_border;
+    let bottom_space_top = origin_y + top_space_height + graph_space_height;
+    let bottom_space_left = origin_x;
     let bottom_space_bottom = bottom_space_top + bottom_space_height - (area_border * 3.0);
     let bottom_space_right = bottom_space_left + bottom_space_width - (area_border * 3.0);
 
@@ -344,7 +755,19 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
 
     let base_line = graph_space_bottom - axis_thickness - x_axis_label_font_height - (2.0 * x_axis_caption_text_gap);
     let vert_size = base_line - graph_space_top;
-    let bar_height_unit_size = vert_size / highest_val as f64;
+
+    // Apply the current rubber-band Y zoom window: the visible value range narrows from the full
+    // [0, highest_val] down to [value_min, value_max], and the bars/markers below are all drawn
+    // relative to that narrowed range rather than the true highest value
+    let value_min = highest_val as f64 * rel_zoom_y_min;
+    let value_max = highest_val as f64 * rel_zoom_y_max;
+    let value_range = value_max - value_min;
+
+    let bar_height_unit_size = if value_range <= 0.0 {
+        0.0
+    } else {
+        vert_size / value_range
+    };
     let bar_label_y = graph_space_bottom;
     let bar_border = 1.0;
     let y_base = base_line + axis_thickness + x_axis_caption_text_gap;
@@ -352,7 +775,7 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
     let y_length = y_base - y_top;
 
     // Calculate the y axis units of measurement
-    let (y_axis_max_value, y_axis_step) = axis_max(highest_val);
+    let (y_axis_max_value, y_axis_step) = axis_max(value_range.round() as u32);
     let y_unit = y_length / y_axis_max_value;
     let y_unit_step = y_unit * y_axis_step;
 
@@ -363,7 +786,7 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
     ctx.set_font(&format!("{}pt serif", y_axis_marker_font_height));
     let mut i = y_base;
     while i >= y_top {
-        let marker_label = &format!("{} ", ((y_base - i) / y_unit).round());
+        let marker_label = &format!("{} ", (value_min + (y_base - i) / y_unit).round());
         let marker_metrics = ctx.measure_text(&marker_label).unwrap();
         let y_axis_marker_width = marker_metrics.width();
         if y_axis_marker_width > y_axis_marker_largest_width {
@@ -407,7 +830,7 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
     ctx.set_text_align(&"right");
     let mut i = y_base;
     while i >= y_top {
-        let marker_label = &format!("{} ", ((y_base - i) / y_unit).round());
+        let marker_label = &format!("{} ", (value_min + (y_base - i) / y_unit).round());
         let marker_metrics = ctx.measure_text(&marker_label).unwrap();
         let y_axis_marker_width = marker_metrics.width();
         ctx.begin_path();
@@ -418,8 +841,8 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
         i -= y_unit_step;
     }
 
-    // Calculate the bar size, gap, and centering based upon the number of bars
-    let num_bars = item_counts.len() as f64;
+    // Calculate the bar size, gap, and centering based upon the number of (currently visible) bars
+    let num_bars = draw_order.len() as f64;
     let horiz_size = graph_space_width - (2.0 * y_axis_marker_largest_width);
     let bar_space = horiz_size / num_bars;
     let bar_width = bar_space * 0.6; // Bars take 60% of the space, gaps between take 40%
@@ -428,27 +851,116 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
     let axis_left = y_marker_x;
     let axis_right = graph_space_right - y_axis_marker_largest_width;
 
-    // Draw simple bar graph using the category data
+    // Record the plot area bounds, so a later rubber-band drag selection can be converted back
+    // into the relative zoom window
+    PLOT_RECT.with(|r| *r.borrow_mut() = (axis_left, axis_right, y_top, y_base));
+
+    // Pre-compute a colour per entry of the legend's key, using the golden-ratio hue sequence, so
+    // the legend swatches (drawn further below) use the exact same colours as the bars.  With a
+    // series column, that key is the series name (consistent across categories, per-series
+    // colouring); otherwise it's the category name, same as a plain single-series chart always did.
+    // Either way the hues are assigned over the full, pre-zoom key set (full_category_order /
+    // series_order are both zoom-independent), so a category's colour doesn't shift depending on
+    // which zoom window happens to be active.
+    let color_keys: Vec<String> = if multi_series {
+        series_order.clone()
+    } else {
+        full_category_order.clone()
+    };
+    let mut palette_colors: Vec<String> = Vec::with_capacity(color_keys.len());
     let mut hue = palette;
+    for _ in &color_keys {
+        hue += GOLDEN_RATIO_CONJUGATE;
+        hue = hue % 1.0;
+        palette_colors.push(hsv_to_rgb(hue, 0.5, 0.95));
+    }
+    let color_lookup: HashMap<&String, &String> = color_keys.iter().zip(palette_colors.iter()).collect();
+
+    // Draw the bar graph using the category/series data
+    let mut bar_hits: Vec<BarHit> = Vec::with_capacity(draw_order.len());
     ctx.set_line_width(bar_border);
     ctx.set_stroke_style(&"black".into());
     ctx.set_text_align(&"center");
     for bar in &draw_order {
-        // Draw the bar
         let label = &bar.name;
-        let num = bar.num;
-        let bar_height = num as f64 * bar_height_unit_size;
-        hue += GOLDEN_RATIO_CONJUGATE;
-        hue = hue % 1.0;
-        ctx.set_fill_style(&hsv_to_rgb(hue, 0.5, 0.95).into());
-        ctx.begin_path();
-        ctx.move_to(bar_left, y_base);
-        ctx.line_to(bar_left + bar_width - bar_border, y_base);
-        ctx.line_to(bar_left + bar_width - bar_border, y_base - bar_height);
-        ctx.line_to(bar_left, y_base - bar_height);
-        ctx.close_path();
-        ctx.fill();
-        ctx.stroke();
+        let color_for = |series_name: &String| -> &String {
+            if multi_series { color_lookup[series_name] } else { color_lookup[label] }
+        };
+
+        // Draw the bar (or, in grouped mode, its row of per-series sub-bars), tracking the tallest
+        // point drawn so the label/count/hit-rectangle above can be positioned against it
+        let bar_top = match bar_mode {
+            BarMode::Stacked => {
+                let total_bar_height = ((bar.total as f64).min(value_max) - value_min).max(0.0) * bar_height_unit_size;
+                let mut cumulative_height = 0.0;
+                for (series_name, count) in &bar.series_values {
+                    if bar.total == 0 || *count == 0 {
+                        continue;
+                    }
+                    let seg_height = total_bar_height * (*count as f64 / bar.total as f64);
+                    ctx.set_fill_style(&color_for(series_name).as_str().into());
+                    ctx.begin_path();
+                    ctx.move_to(bar_left, y_base - cumulative_height);
+                    ctx.line_to(bar_left + bar_width - bar_border, y_base - cumulative_height);
+                    ctx.line_to(bar_left + bar_width - bar_border, y_base - cumulative_height - seg_height);
+                    ctx.line_to(bar_left, y_base - cumulative_height - seg_height);
+                    ctx.close_path();
+                    ctx.fill();
+                    ctx.stroke();
+                    cumulative_height += seg_height;
+                }
+                y_base - cumulative_height
+            }
+            BarMode::Grouped => {
+                let n_series = (bar.series_values.len() as f64).max(1.0);
+                let sub_width = bar_width / n_series;
+                let mut tallest = 0.0;
+                for (i, (series_name, count)) in bar.series_values.iter().enumerate() {
+                    let seg_height = ((*count as f64).min(value_max) - value_min).max(0.0) * bar_height_unit_size;
+                    let seg_left = bar_left + (i as f64 * sub_width);
+                    ctx.set_fill_style(&color_for(series_name).as_str().into());
+                    ctx.begin_path();
+                    ctx.move_to(seg_left, y_base);
+                    ctx.line_to(seg_left + sub_width - bar_border, y_base);
+                    ctx.line_to(seg_left + sub_width - bar_border, y_base - seg_height);
+                    ctx.line_to(seg_left, y_base - seg_height);
+                    ctx.close_path();
+                    ctx.fill();
+                    ctx.stroke();
+                    // Record each sub-bar's own hit-rectangle and count, so hovering over it
+                    // shows that series' value rather than the category's summed total
+                    bar_hits.push(BarHit {
+                        name: format!("{} ({})", label, series_name),
+                        count: *count,
+                        left: seg_left,
+                        right: seg_left + sub_width - bar_border,
+                        top: y_base - seg_height,
+                    });
+                    // Label each sub-bar with its own count, centered above it — the category-wide
+                    // total printed for Stacked mode would match no single bar drawn here
+                    ctx.set_fill_style(&"black".into());
+                    ctx.set_font(&format!("{}pt serif", x_count_font_height));
+                    ctx.fill_text(
+                        &format!("{}", count),
+                        seg_left + (sub_width - bar_border) / 2.0,
+                        y_base - seg_height - x_axis_caption_text_gap,
+                    );
+                    if seg_height > tallest {
+                        tallest = seg_height;
+                    }
+                }
+                y_base - tallest
+            }
+        };
+        if bar_mode == BarMode::Stacked {
+            bar_hits.push(BarHit {
+                name: label.clone(),
+                count: bar.total,
+                left: bar_left,
+                right: bar_left + bar_width - bar_border,
+                top: bar_top,
+            });
+        }
         ctx.set_fill_style(&"black".into());
 
         // Draw the bar label horizontally centered
@@ -472,13 +984,16 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
             ctx.restore();
         }
 
-        // Draw the item count centered above the top of the bar
-        ctx.set_font(&format!("{}pt serif", x_count_font_height));
-        ctx.fill_text(
-            &format!("{}", num),
-            text_left,
-            y_base - bar_height - x_axis_caption_text_gap,
-        );
+        // Draw the bar's total, centered above its tallest point.  Grouped mode already labeled
+        // each sub-bar with its own count above, so the summed total has nothing to anchor to here
+        if bar_mode == BarMode::Stacked {
+            ctx.set_font(&format!("{}pt serif", x_count_font_height));
+            ctx.fill_text(
+                &format!("{}", bar.total),
+                text_left,
+                bar_top - x_axis_caption_text_gap,
+            );
+        }
         bar_left += bar_gap + bar_width;
     }
 
@@ -491,16 +1006,7 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
     ctx.stroke();
 
     // Draw title
-    let mut title = data.Title.as_str();
-    if title.ends_with(".sqlite") {
-        title = title.trim_end_matches(".sqlite")
-    }
-    if title.ends_with(".sqlite3") {
-        title = title.trim_end_matches(".sqlite3")
-    }
-    if title.ends_with(".db") {
-        title = title.trim_end_matches(".db")
-    }
+    let title = trim_title(data.Title.as_str());
     ctx.set_font(&format!("bold {}pt serif", title_font_height));
     ctx.set_fill_style(&"black".into());
     ctx.set_text_align(&"center");
@@ -516,7 +1022,7 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
     let y_axis_caption_metrics = ctx.measure_text(&y_axis_caption_string).unwrap();
     let y_axis_caption_width = y_axis_caption_metrics.width().round();
     let spin_x = (left_space_left + (left_space_width / 2.0)) + y_axis_caption_font_height;
-    let spin_y = (canvas_height / 2.0) - axis_thickness - x_axis_label_font_height;
+    let spin_y = origin_y + (display_height / 2.0) - axis_thickness - x_axis_label_font_height;
     ctx.save();
     ctx.translate(spin_x, spin_y);
     ctx.rotate(3.0 * std::f64::consts::PI / 2.0);
@@ -575,12 +1081,133 @@ pub fn draw_bar_chart(palette: f64, js_data: &JsValue, order_by: u32, order_dire
     ctx.set_line_width(2.0);
     ctx.set_stroke_style(&"black".into());
     ctx.begin_path();
-    ctx.move_to(border, border);
-    ctx.line_to(display_width, border);
-    ctx.line_to(display_width, display_height);
-    ctx.line_to(border, display_height);
+    ctx.move_to(origin_x, origin_y);
+    ctx.line_to(origin_x + display_width, origin_y);
+    ctx.line_to(origin_x + display_width, origin_y + display_height);
+    ctx.line_to(origin_x, origin_y + display_height);
     ctx.close_path();
     ctx.stroke();
+
+    // Draw the legend, if one was requested.  Colours come from the full, zoom-independent key set
+    // above, but entries are limited to what's actually drawn: every series in the multi-series
+    // case (each category shows all of them), or just the categories in the current zoom window
+    // otherwise.
+    if let (Some(legend), Some(rect)) = (&legend, legend_rect) {
+        let legend_entries: Vec<(String, String)> = if multi_series {
+            color_keys.iter().cloned().zip(palette_colors.iter().cloned()).collect()
+        } else {
+            draw_order
+                .iter()
+                .map(|bar| (bar.name.clone(), color_lookup[&bar.name].clone()))
+                .collect()
+        };
+        draw_legend(&ctx, legend, rect, &legend_entries, y_axis_marker_font_height);
+    }
+
+    // Cache the rendered chart and each bar's hit-rectangle, so the hover handler can restore the
+    // base image and overlay a crosshair/tooltip without redoing this whole layout
+    BAR_HITS.with(|h| *h.borrow_mut() = bar_hits);
+    if let Ok(image) = ctx.get_image_data(0.0, 0.0, canvas_width, canvas_height) {
+        BASE_IMAGE.with(|b| *b.borrow_mut() = Some(image));
+    }
+}
+
+// draw_pie_chart draws a pie (or doughnut, when inner_radius > 0) chart from the same category
+// aggregation draw_bar_chart uses, giving a proportion view for categorical columns that the bar
+// renderer can't express well.
+#[wasm_bindgen]
+pub fn draw_pie_chart(palette: f64, js_data: &JsValue, inner_radius: f64, cat_col: u32, val_col: u32) {
+    // Show better panic messages on the javascript console.  Useful for development
+    panic::set_hook(Box::new(console_error_panic_hook::hook));
+
+    // * Import the data from the web page *
+    let data: DbData = js_data.into_serde().unwrap();
+    assert!((cat_col as i32) < data.ColCount, "cat_col is beyond the result set's column count");
+    assert!((val_col as i32) < data.ColCount, "val_col is beyond the result set's column count");
+    let cat_col = cat_col as usize;
+    let val_col = val_col as usize;
+    let rows = data.Records;
+
+    // Count the number of items for each category
+    let (item_counts, _highest_val) = build_item_counts(&rows, cat_col, val_col);
+    let total: u32 = item_counts.values().sum();
+
+    // * Canvas setup *
+
+    let (_canvas, ctx, canvas_width, canvas_height) = setup_canvas("barchart");
+
+    // Clear the background
+    ctx.set_fill_style(&"white".into());
+    ctx.fill_rect(0.0, 0.0, canvas_width, canvas_height);
+
+    // Draw title
+    let title = trim_title(data.Title.as_str());
+    let area_root = (canvas_height * canvas_width).sqrt();
+    let title_font_height = area_root * 0.025;
+    let label_font_height = area_root * 0.015;
+    ctx.set_font(&format!("bold {}pt serif", title_font_height));
+    ctx.set_fill_style(&"black".into());
+    ctx.set_text_align(&"center");
+    ctx.fill_text(title, canvas_width / 2.0, title_font_height + 4.0);
+
+    if total == 0 {
+        return;
+    }
+
+    // The pie is centered in the canvas, below the title, and sized to fit whichever of the
+    // remaining width/height is smaller
+    let center_x = canvas_width / 2.0;
+    let center_y = (canvas_height + title_font_height) / 2.0;
+    let radius = (canvas_width.min(canvas_height - title_font_height) / 2.0) * 0.8;
+    let inner_radius = inner_radius.max(0.0).min(radius);
+
+    // Sort by category name, so the draw order (and thus wedge colours) doesn't change when the
+    // browser window is resized
+    let mut draw_order: Vec<DrawObject> = vec![];
+    for (label, num) in &item_counts {
+        draw_order.push(DrawObject::new(label.to_string(), *num));
+    }
+    draw_order.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Draw each category as a wedge, sized proportionally to its share of the total
+    let mut hue = palette;
+    let mut angle = -std::f64::consts::FRAC_PI_2; // Start at the top of the circle
+    ctx.set_line_width(1.0);
+    ctx.set_stroke_style(&"black".into());
+    for item in &draw_order {
+        let fraction = item.num as f64 / total as f64;
+        let sweep = 2.0 * std::f64::consts::PI * fraction;
+        let end_angle = angle + sweep;
+
+        hue += GOLDEN_RATIO_CONJUGATE;
+        hue = hue % 1.0;
+        ctx.set_fill_style(&hsv_to_rgb(hue, 0.5, 0.95).into());
+
+        ctx.begin_path();
+        if inner_radius > 0.0 {
+            // Doughnut wedge: an outer arc and an inner arc joined at both ends
+            ctx.arc(center_x, center_y, radius, angle, end_angle).unwrap();
+            ctx.arc_with_anticlockwise(center_x, center_y, inner_radius, end_angle, angle, true).unwrap();
+        } else {
+            ctx.move_to(center_x, center_y);
+            ctx.arc(center_x, center_y, radius, angle, end_angle).unwrap();
+        }
+        ctx.close_path();
+        ctx.fill();
+        ctx.stroke();
+
+        // Place the percentage label at the wedge centroid
+        let mid_angle = angle + (sweep / 2.0);
+        let label_radius = inner_radius + ((radius - inner_radius) * 0.7);
+        let label_x = center_x + (label_radius * mid_angle.cos());
+        let label_y = center_y + (label_radius * mid_angle.sin());
+        ctx.set_fill_style(&"black".into());
+        ctx.set_font(&format!("{}pt serif", label_font_height));
+        ctx.set_text_align(&"center");
+        ctx.fill_text(&format!("{:.0}%", fraction * 100.0), label_x, label_y);
+
+        angle = end_angle;
+    }
 }
 
 // Ported from the JS here: https://martin.ankerl.com/2009/12/09/how-to-create-random-colors-programmatically/
@@ -632,23 +1259,48 @@ fn hsv_to_rgb(h: f64, s: f64, v: f64) -> String {
     return format!("rgb({}, {}, {})", red, green, blue);
 }
 
-// axis_max calculates the maximum value for a given axis, and the step value to use when drawing its grid lines
+// nicenum rounds x to a "nice" number: 1, 2, 5 or 10 times a power of 10.  When round is true the
+// nearest nice number is used (for picking a tick spacing), otherwise the next nice number >= x is
+// used (for picking an axis range).  Ported from Paul Heckbert's "Nice Numbers for Graph Labels"
+// (Graphics Gems, 1990).
+fn nicenum(x: f64, round: bool) -> f64 {
+    let exp = x.log10().floor();
+    let frac = x / 10f64.powf(exp);
+
+    let nf = if round {
+        if frac < 1.5 {
+            1.0
+        } else if frac < 3.0 {
+            2.0
+        } else if frac < 7.0 {
+            5.0
+        } else {
+            10.0
+        }
+    } else if frac <= 1.0 {
+        1.0
+    } else if frac <= 2.0 {
+        2.0
+    } else if frac <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nf * 10f64.powf(exp)
+}
+
+// axis_max calculates the maximum value for a given axis, and the step value to use when drawing
+// its grid lines, using Heckbert's "nice numbers" tick selection so it scales to any magnitude
+// instead of being capped at a hard-coded ceiling.
 fn axis_max(val: u32) -> (f64, f64) {
-    let val = val as f64;
-    if val < 10.0 {
+    if val == 0 {
         return (10.0, 1.0);
     }
 
-    // If val is less than 100, return val rounded up to the next 10
-    if val < 100.0 {
-        let x = val % 10.0;
-        return (val + 10.0 - x, 10.0);
-    }
-
-    // If val is less than 500, return val rounded up to the next 50
-    if val < 500.0 {
-        let x = val % 50.0;
-        return (val + 50.0 - x, 50.0);
-    }
-    (1000.0, 100.0)
+    let val = val as f64;
+    let num_ticks = 5.0;
+    let range = nicenum(val, false);
+    let d = nicenum(range / (num_ticks - 1.0), true);
+    let axis_top = (val / d).ceil() * d;
+    (axis_top, d)
 }